@@ -1,43 +1,61 @@
 use axum::{
     extract::State,
     http::StatusCode,
-    response::{Html, IntoResponse},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html, IntoResponse,
+    },
     routing::{get, post},
     Json, Router,
 };
+use futures::future::join_all;
+use futures::stream::Stream;
 use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::pin::Pin;
 use tokio::net::TcpListener;
+use tokio::sync::mpsc as tokio_mpsc;
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::StreamExt;
 use tracing::{error, info};
 
-use anyhow::{bail, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::Parser;
 use llama_cpp_2::context::params::LlamaContextParams;
+use llama_cpp_2::context::LlamaContext;
 use llama_cpp_2::llama_backend::LlamaBackend;
 use llama_cpp_2::llama_batch::LlamaBatch;
 use llama_cpp_2::model::params::LlamaModelParams;
 use llama_cpp_2::model::{AddBos, LlamaModel, Special};
 use llama_cpp_2::sampling::LlamaSampler;
+use llama_cpp_2::token::LlamaToken;
 use llama_cpp_2::{send_logs_to_tracing, LogOptions};
 
+use std::collections::{HashMap, VecDeque};
 use std::num::NonZeroU32;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc as std_mpsc;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 
 #[derive(clap::Parser, Debug, Clone)]
 struct Args {
-    /// The path to the kulyk-uk-en model
-    #[arg(long, help = "Path to kulyk-uk-en model")]
-    model_path_ue: PathBuf,
-
-    /// The path to the kulyk-en-uk model
-    #[arg(long, help = "Path to kulyk-en-uk model")]
-    model_path_eu: PathBuf,
+    /// Path to the backend registry config (JSON or TOML) describing the
+    /// models to load and the language pairs each one serves
+    #[arg(long, help = "Path to the model registry config (JSON or TOML)")]
+    config: PathBuf,
 
     /// Set the length of the prompt + output in tokens
     #[arg(long, default_value_t = 32)]
     n_len: i32,
 
+    /// Number of requests a single context can serve concurrently
+    #[arg(
+        long,
+        help = "number of parallel decoding slots per model (default: 4)"
+    )]
+    n_parallel: Option<u32>,
+
     /// Disable offloading layers to the gpu
     #[cfg(any(feature = "cuda", feature = "vulkan"))]
     #[clap(long)]
@@ -47,6 +65,26 @@ struct Args {
     #[arg(short = 's', long, help = "RNG seed (default: 1234)")]
     seed: Option<u32>,
 
+    /// Sampling temperature; 0 means greedy decoding
+    #[arg(long, help = "sampling temperature, 0 = greedy (default: 0.0)")]
+    temperature: Option<f32>,
+
+    /// Top-k sampling cutoff
+    #[arg(long, help = "top-k sampling cutoff (default: 40)")]
+    top_k: Option<i32>,
+
+    /// Top-p (nucleus) sampling cutoff
+    #[arg(long, help = "top-p sampling cutoff (default: 0.95)")]
+    top_p: Option<f32>,
+
+    /// Min-p sampling cutoff
+    #[arg(long, help = "min-p sampling cutoff (default: 0.05)")]
+    min_p: Option<f32>,
+
+    /// Repetition penalty applied to previously generated tokens
+    #[arg(long, help = "repeat penalty (default: 1.1)")]
+    repeat_penalty: Option<f32>,
+
     /// Number of threads
     #[arg(
         short = 't',
@@ -66,7 +104,7 @@ struct Args {
     #[arg(
         short = 'c',
         long,
-        help = "size of the prompt context (default: loaded from themodel)"
+        help = "size of the prompt context per slot (default: 2048)"
     )]
     ctx_size: Option<NonZeroU32>,
 
@@ -79,147 +117,671 @@ struct Args {
     port: Option<u16>,
 }
 
-struct TranslationModel {
-    args: Args,
-    model_ue: LlamaModel,
-    backend: LlamaBackend,
-    model_eu: LlamaModel,
+fn model_params(_args: &Args, _gpu_layers: Option<u32>) -> LlamaModelParams {
+    #[cfg(any(feature = "cuda", feature = "vulkan"))]
+    if !_args.disable_gpu {
+        return LlamaModelParams::default().with_n_gpu_layers(_gpu_layers.unwrap_or(1000));
+    }
+    LlamaModelParams::default()
 }
 
-impl TranslationModel {
-    fn new(args: Args) -> Result<Self> {
-        let backend = LlamaBackend::init()?;
-
-        let model_ue = {
-            let model_params = {
-                #[cfg(any(feature = "cuda", feature = "vulkan"))]
-                if !args.disable_gpu {
-                    LlamaModelParams::default().with_n_gpu_layers(1000)
-                } else {
-                    LlamaModelParams::default()
-                }
-                #[cfg(not(any(feature = "cuda", feature = "vulkan")))]
-                LlamaModelParams::default()
-            };
-            LlamaModel::load_from_file(&backend, &args.model_path_ue, &model_params)
-                .with_context(|| "unable to load uk-en model")?
-        };
+/// A language direction one registry entry serves, e.g. `uk` -> `en`.
+#[derive(Deserialize, Debug, Clone)]
+struct LangPair {
+    source_lang: String,
+    target_lang: String,
+}
 
-        let model_eu = {
-            let model_params = {
-                #[cfg(any(feature = "cuda", feature = "vulkan"))]
-                if !args.disable_gpu {
-                    LlamaModelParams::default().with_n_gpu_layers(1000)
-                } else {
-                    LlamaModelParams::default()
-                }
-                #[cfg(not(any(feature = "cuda", feature = "vulkan")))]
-                LlamaModelParams::default()
-            };
-            LlamaModel::load_from_file(&backend, &args.model_path_eu, &model_params)
-                .with_context(|| "unable to load en-uk model")?
-        };
+/// One model's registry entry: where to load it from, which directions it
+/// serves, its prompt template, and its own context/GPU settings. Context and
+/// thread fields left out fall back to the server-wide CLI defaults in
+/// [`Args`]; `gpu_layers` left out offloads up to 1000 layers.
+#[derive(Deserialize, Debug, Clone)]
+struct ModelConfig {
+    path: PathBuf,
+    pairs: Vec<LangPair>,
+    prompt_template: String,
+    /// Client-facing model id for the `/v1/chat/completions` facade, e.g.
+    /// `kulyk-uk-en`. Defaults to the joined `source-target` pairs.
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    n_parallel: Option<u32>,
+    #[serde(default)]
+    ctx_size: Option<NonZeroU32>,
+    #[serde(default)]
+    threads: Option<i32>,
+    #[serde(default)]
+    threads_batch: Option<i32>,
+    #[serde(default)]
+    gpu_layers: Option<u32>,
+}
 
-        Ok(Self {
-            args,
-            model_ue,
-            backend,
-            model_eu,
-        })
+#[derive(Deserialize, Debug, Clone)]
+struct RegistryConfig {
+    models: Vec<ModelConfig>,
+}
+
+fn load_registry_config(path: &Path) -> Result<RegistryConfig> {
+    let raw = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file {}", path.display()))?;
+
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => {
+            toml::from_str(&raw).with_context(|| format!("failed to parse TOML config {}", path.display()))
+        }
+        _ => serde_json::from_str(&raw)
+            .with_context(|| format!("failed to parse JSON config {}", path.display())),
     }
+}
 
-    fn translate(&self, text: &str, source_lang: &str, target_lang: &str) -> Result<String> {
-        info!(
-            "Translating text from '{}' to '{}'",
-            source_lang, target_lang
-        );
+/// Resolved, validated decoding parameters for a single request, merging
+/// whatever the caller supplied over the CLI defaults from [`Args`].
+#[derive(Debug, Clone, Copy)]
+struct SamplingParams {
+    temperature: f32,
+    top_k: i32,
+    top_p: f32,
+    min_p: f32,
+    repeat_penalty: f32,
+    seed: u32,
+}
 
-        let prompt = fill_prompt(text, target_lang);
-        info!("prompt: {}", prompt);
+impl SamplingParams {
+    fn resolve(args: &Args, req: &TranslateRequest) -> Result<Self> {
+        let params = Self {
+            temperature: req.temperature.unwrap_or(args.temperature.unwrap_or(0.0)),
+            top_k: req.top_k.unwrap_or(args.top_k.unwrap_or(40)),
+            top_p: req.top_p.unwrap_or(args.top_p.unwrap_or(0.95)),
+            min_p: req.min_p.unwrap_or(args.min_p.unwrap_or(0.05)),
+            repeat_penalty: req
+                .repeat_penalty
+                .unwrap_or(args.repeat_penalty.unwrap_or(1.1)),
+            seed: req.seed.unwrap_or(args.seed.unwrap_or(1234)),
+        };
 
-        let model_to_use = if source_lang == "uk" && target_lang == "en" {
-            &self.model_ue
-        } else if source_lang == "en" && target_lang == "uk" {
-            &self.model_eu
+        if params.temperature < 0.0 {
+            bail!("temperature must be >= 0");
+        }
+        if params.top_k < 0 {
+            bail!("top_k must be >= 0");
+        }
+        if !(0.0..=1.0).contains(&params.top_p) {
+            bail!("top_p must be between 0 and 1");
+        }
+        if !(0.0..=1.0).contains(&params.min_p) {
+            bail!("min_p must be between 0 and 1");
+        }
+        if params.repeat_penalty <= 0.0 {
+            bail!("repeat_penalty must be > 0");
+        }
+
+        Ok(params)
+    }
+
+    /// Builds the sampler chain for these parameters: plain greedy decoding
+    /// when temperature is 0, otherwise top-k/top-p/min-p/penalty/temp
+    /// followed by the final distribution sample.
+    fn build_sampler(&self) -> LlamaSampler {
+        if self.temperature <= 0.0 {
+            LlamaSampler::chain_simple([LlamaSampler::dist(self.seed), LlamaSampler::greedy()])
         } else {
-            bail!(
-                "Unsupported translation direction: {} to {}",
-                source_lang,
-                target_lang
-            );
-        };
+            LlamaSampler::chain_simple([
+                LlamaSampler::top_k(self.top_k),
+                LlamaSampler::top_p(self.top_p, 1),
+                LlamaSampler::min_p(self.min_p, 1),
+                LlamaSampler::penalties(64, self.repeat_penalty, 0.0, 0.0),
+                LlamaSampler::temp(self.temperature),
+                LlamaSampler::dist(self.seed),
+            ])
+        }
+    }
+}
+
+/// A decode request queued onto a model's [`SlotScheduler`].
+struct GenerationRequest {
+    prompt: String,
+    n_len: i32,
+    sampling: SamplingParams,
+    /// One `Ok` fragment per generated token; the channel is dropped when
+    /// generation finishes, an `Err` fragment is sent on failure.
+    fragment_tx: tokio_mpsc::UnboundedSender<Result<String, String>>,
+}
 
-        self.translate_text(model_to_use, prompt)
+enum SlotState {
+    Idle,
+    Prefill { tokens: Vec<LlamaToken> },
+    Generating,
+}
+
+struct Slot {
+    seq_id: i32,
+    n_cur: i32,
+    n_len: i32,
+    state: SlotState,
+    sampler: Option<LlamaSampler>,
+    decoder: encoding_rs::Decoder,
+    logit_index: Option<i32>,
+    pending_token: Option<LlamaToken>,
+    fragment_tx: Option<tokio_mpsc::UnboundedSender<Result<String, String>>>,
+}
+
+impl Slot {
+    fn idle(seq_id: i32) -> Self {
+        Self {
+            seq_id,
+            n_cur: 0,
+            n_len: 0,
+            state: SlotState::Idle,
+            sampler: None,
+            decoder: encoding_rs::UTF_8.new_decoder(),
+            logit_index: None,
+            pending_token: None,
+            fragment_tx: None,
+        }
     }
 
-    fn translate_text(&self, model: &LlamaModel, prompt: String) -> Result<String> {
-        let args = &self.args;
+    fn is_idle(&self) -> bool {
+        matches!(self.state, SlotState::Idle)
+    }
+
+    fn activate(&mut self, req: GenerationRequest, tokens: Vec<LlamaToken>) {
+        self.n_cur = 0;
+        self.n_len = req.n_len;
+        self.sampler = Some(req.sampling.build_sampler());
+        self.decoder = encoding_rs::UTF_8.new_decoder();
+        self.logit_index = None;
+        self.pending_token = None;
+        self.fragment_tx = Some(req.fragment_tx);
+        self.state = SlotState::Prefill { tokens };
+    }
+
+    fn fragment_tx(&self) -> &tokio_mpsc::UnboundedSender<Result<String, String>> {
+        self.fragment_tx
+            .as_ref()
+            .expect("fragment_tx is set for any non-idle slot")
+    }
+
+    fn reset(&mut self) {
+        self.state = SlotState::Idle;
+        self.sampler = None;
+        self.logit_index = None;
+        self.pending_token = None;
+        self.fragment_tx = None;
+        self.n_cur = 0;
+        self.n_len = 0;
+    }
+}
+
+/// Owns one long-lived [`LlamaContext`] for a model and serves every
+/// translation request through it, multiplexing concurrent callers onto
+/// a fixed pool of sequence-id slots that share a single KV cache.
+#[derive(Clone)]
+struct SlotScheduler {
+    tx: std_mpsc::Sender<GenerationRequest>,
+}
+
+impl SlotScheduler {
+    fn spawn(
+        model: Arc<LlamaModel>,
+        backend: Arc<LlamaBackend>,
+        n_slots: u32,
+        ctx_size: Option<NonZeroU32>,
+        threads: Option<i32>,
+        threads_batch: Option<i32>,
+        name: String,
+    ) -> Result<Self> {
+        let (req_tx, req_rx) = std_mpsc::channel::<GenerationRequest>();
+        let (ready_tx, ready_rx) = std_mpsc::channel::<Result<()>>();
 
-        let mut ctx_params = LlamaContextParams::default()
-            .with_n_ctx(args.ctx_size.or(Some(NonZeroU32::new(2048).unwrap())));
-        if let Some(threads) = args.threads {
-            ctx_params = ctx_params.with_n_threads(threads);
+        std::thread::Builder::new()
+            .name(format!("translate-scheduler-{name}"))
+            .spawn(move || {
+                scheduler_loop(model, backend, n_slots, ctx_size, threads, threads_batch, req_rx, ready_tx)
+            })
+            .with_context(|| format!("failed to spawn '{name}' scheduler thread"))?;
+
+        match ready_rx.recv() {
+            Ok(Ok(())) => Ok(Self { tx: req_tx }),
+            Ok(Err(e)) => Err(e),
+            Err(_) => bail!("'{name}' scheduler thread terminated before it was ready"),
         }
-        if let Some(threads_batch) = args.threads_batch.or(args.threads) {
-            ctx_params = ctx_params.with_n_threads_batch(threads_batch);
+    }
+
+    fn submit(&self, req: GenerationRequest) -> Result<()> {
+        self.tx
+            .send(req)
+            .map_err(|_| anyhow!("scheduler thread is no longer running"))
+    }
+}
+
+fn finish_slot(ctx: &mut LlamaContext, slot: &mut Slot) {
+    ctx.clear_kv_cache_seq(Some(slot.seq_id), None, None);
+    slot.reset();
+}
+
+/// What a single slot wants to contribute to this round's [`LlamaBatch`].
+enum BatchOp {
+    Prefill(Vec<LlamaToken>),
+    Continue(LlamaToken, i32),
+}
+
+/// Adds `op` to `batch` under `seq_id`, logging and returning `false` on
+/// failure. The caller must not let a `false` result reach `ctx.decode`: a
+/// partially-added prefill would leave orphaned entries for `seq_id` in the
+/// batch even after its slot has been reset.
+fn commit_batch_op(batch: &mut LlamaBatch, seq_id: i32, op: &BatchOp) -> bool {
+    let result = match op {
+        BatchOp::Prefill(tokens) => {
+            let last = tokens.len() - 1;
+            tokens
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, &tok)| batch.add(tok, i as i32, &[seq_id], i == last))
         }
+        BatchOp::Continue(token, n_cur) => batch.add(*token, *n_cur, &[seq_id], true),
+    };
+    if let Err(e) = result {
+        error!("failed to add tokens to batch: {:?}", e);
+        false
+    } else {
+        true
+    }
+}
 
-        let mut ctx = model
-            .new_context(&self.backend, ctx_params)
-            .with_context(|| "unable to create the llama_context")?;
+fn scheduler_loop(
+    model: Arc<LlamaModel>,
+    backend: Arc<LlamaBackend>,
+    n_slots: u32,
+    ctx_size: Option<NonZeroU32>,
+    threads: Option<i32>,
+    threads_batch: Option<i32>,
+    rx: std_mpsc::Receiver<GenerationRequest>,
+    ready_tx: std_mpsc::Sender<Result<()>>,
+) {
+    let n_ctx_per_slot = ctx_size.map(NonZeroU32::get).unwrap_or(2048);
+    let n_ctx_total = n_ctx_per_slot.saturating_mul(n_slots).max(1);
+
+    let mut ctx_params = LlamaContextParams::default()
+        .with_n_ctx(NonZeroU32::new(n_ctx_total))
+        .with_n_seq_max(n_slots);
+    if let Some(t) = threads {
+        ctx_params = ctx_params.with_n_threads(t);
+    }
+    if let Some(tb) = threads_batch.or(threads) {
+        ctx_params = ctx_params.with_n_threads_batch(tb);
+    }
+
+    let mut ctx = match model
+        .new_context(&backend, ctx_params)
+        .with_context(|| "unable to create the llama_context")
+    {
+        Ok(ctx) => {
+            let _ = ready_tx.send(Ok(()));
+            ctx
+        }
+        Err(e) => {
+            let _ = ready_tx.send(Err(e));
+            return;
+        }
+    };
 
-        let tokens_list = model
-            .str_to_token(&prompt, AddBos::Always)
-            .with_context(|| format!("failed to tokenize {prompt}"))?;
+    let mut slots: Vec<Slot> = (0..n_slots as i32).map(Slot::idle).collect();
+    // Sized to `n_ctx_total` so that even the worst case of every slot
+    // prefilling its full per-slot context budget in the same round fits
+    // without the batch overflowing partway through a slot's tokens.
+    let mut batch = LlamaBatch::new(n_ctx_total as usize, 1);
 
-        let n_cxt = ctx.n_ctx() as i32;
-        let n_kv_req = tokens_list.len() as i32 + (args.n_len - tokens_list.len() as i32);
+    // Requests that arrived while every slot was busy. They are not
+    // rejected; they wait here and are admitted in FIFO order as slots
+    // free up, so callers beyond `n_slots` are served rather than bounced.
+    let mut pending: VecDeque<GenerationRequest> = VecDeque::new();
 
-        if n_kv_req > n_cxt {
-            bail!(
-                "n_kv_req > n_ctx, the required kv cache size is not big enough; either reduce n_len or increase n_ctx"
-            );
+    loop {
+        if slots.iter().all(Slot::is_idle) && pending.is_empty() {
+            match rx.recv() {
+                Ok(req) => pending.push_back(req),
+                Err(_) => return,
+            }
         }
-        if tokens_list.len() >= usize::try_from(args.n_len)? {
-            bail!("the prompt is too long, it has more tokens than n_len");
+        while let Ok(req) = rx.try_recv() {
+            pending.push_back(req);
         }
 
-        let mut batch = LlamaBatch::new(512, 1);
-        let last_index: i32 = (tokens_list.len() - 1) as i32;
-        for (i, token) in (0_i32..).zip(tokens_list.into_iter()) {
-            let is_last = i == last_index;
-            batch.add(token, i, &[0], is_last)?;
+        while let Some(slot) = slots.iter_mut().find(|s| s.is_idle()) {
+            let Some(req) = pending.pop_front() else {
+                break;
+            };
+            match model.str_to_token(&req.prompt, AddBos::Always) {
+                Ok(tokens)
+                    if !tokens.is_empty()
+                        && (tokens.len() as i32) < req.n_len
+                        && tokens.len() as u32 + req.n_len as u32 <= n_ctx_per_slot =>
+                {
+                    slot.activate(req, tokens);
+                }
+                Ok(tokens) if tokens.is_empty() || (tokens.len() as i32) >= req.n_len => {
+                    let _ = req
+                        .fragment_tx
+                        .send(Err("the prompt is too long, it has more tokens than n_len".into()));
+                }
+                Ok(_) => {
+                    let _ = req.fragment_tx.send(Err(format!(
+                        "the prompt plus n_len would exceed this model's per-slot context budget of {n_ctx_per_slot} tokens"
+                    )));
+                }
+                Err(e) => {
+                    let _ = req.fragment_tx.send(Err(format!("failed to tokenize prompt: {e}")));
+                }
+            }
         }
 
-        ctx.decode(&mut batch)
-            .with_context(|| "llama_decode() failed")?;
+        // Build this round's batch without committing any slot state yet,
+        // so that a failed decode can be retried after evicting only the
+        // slot responsible instead of tearing down every active slot.
+        batch.clear();
+        // Snapshot what each slot wants to contribute before touching the
+        // batch, so the mutation pass below never needs to hold a
+        // reference into `slots` across a mutable borrow.
+        let plan: Vec<(usize, i32, BatchOp)> = slots
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, slot)| match &slot.state {
+                SlotState::Idle => None,
+                SlotState::Prefill { tokens } => Some((idx, slot.seq_id, BatchOp::Prefill(tokens.clone()))),
+                SlotState::Generating => slot
+                    .pending_token
+                    .map(|token| (idx, slot.seq_id, BatchOp::Continue(token, slot.n_cur))),
+            })
+            .collect();
 
-        let mut n_cur = batch.n_tokens();
-        let mut decoder = encoding_rs::UTF_8.new_decoder();
-        let mut sampler = LlamaSampler::chain_simple([
-            LlamaSampler::dist(args.seed.unwrap_or(1234)),
-            LlamaSampler::greedy(),
-        ]);
-        let mut output = String::new();
+        let mut contributions: Vec<(usize, i32)> = Vec::new();
+        // Ops that have actually landed in `batch` so far this round, kept
+        // around so a failed add can be rolled back to a clean state
+        // instead of leaving that slot's partial tokens in `batch`.
+        let mut committed: Vec<(usize, i32, BatchOp)> = Vec::new();
+        for (idx, seq_id, op) in plan {
+            if commit_batch_op(&mut batch, seq_id, &op) {
+                contributions.push((idx, batch.n_tokens() - 1));
+                committed.push((idx, seq_id, op));
+            } else {
+                // A multi-token prefill can fail partway through, leaving
+                // some of its tokens already in `batch` under this slot's
+                // seq_id. Rebuild the batch from only the contributions
+                // that fully landed so none of that slot's orphaned tokens
+                // reach `ctx.decode` once the slot's KV cache is cleared.
+                batch.clear();
+                contributions.clear();
+                for (committed_idx, committed_seq_id, committed_op) in &committed {
+                    commit_batch_op(&mut batch, *committed_seq_id, committed_op);
+                    contributions.push((*committed_idx, batch.n_tokens() - 1));
+                }
+                let slot = &mut slots[idx];
+                let _ = slot.fragment_tx().send(Err("failed to queue this request".into()));
+                finish_slot(&mut ctx, slot);
+            }
+        }
 
-        while n_cur <= args.n_len {
-            let token = sampler.sample(&ctx, batch.n_tokens() - 1);
+        if batch.n_tokens() == 0 {
+            continue;
+        }
+
+        if let Err(e) = ctx.decode(&mut batch) {
+            error!("llama_decode() failed: {:?}", e);
+            // A single runaway slot overflowing the shared KV cache is the
+            // most likely cause; evict only the slot that had advanced the
+            // furthest and let the others retry on the next iteration
+            // instead of failing every concurrent caller.
+            if let Some(&(idx, _)) = contributions.iter().max_by_key(|(idx, _)| slots[*idx].n_cur) {
+                let slot = &mut slots[idx];
+                let _ = slot
+                    .fragment_tx()
+                    .send(Err(format!("llama_decode() failed, dropping this request: {e}")));
+                finish_slot(&mut ctx, slot);
+            }
+            continue;
+        }
+
+        for (idx, logit_index) in contributions {
+            let slot = &mut slots[idx];
+            match &slot.state {
+                SlotState::Prefill { tokens } => {
+                    slot.n_cur = tokens.len() as i32;
+                    slot.state = SlotState::Generating;
+                }
+                SlotState::Generating => slot.n_cur += 1,
+                SlotState::Idle => unreachable!("idle slots never contribute to the batch"),
+            }
+            slot.logit_index = Some(logit_index);
+        }
+
+        for slot in slots.iter_mut() {
+            if slot.is_idle() {
+                continue;
+            }
+            let Some(logit_index) = slot.logit_index.take() else {
+                continue;
+            };
+
+            let sampler = slot.sampler.as_mut().expect("generating slots carry a sampler");
+            let token = sampler.sample(&ctx, logit_index);
             sampler.accept(token);
-            if model.is_eog_token(token) {
-                break;
+
+            if model.is_eog_token(token) || slot.n_cur >= slot.n_len {
+                finish_slot(&mut ctx, slot);
+                continue;
+            }
+
+            match model.token_to_bytes(token, Special::Tokenize) {
+                Ok(bytes) => {
+                    let mut piece = String::with_capacity(32);
+                    let _ = slot.decoder.decode_to_string(&bytes, &mut piece, false);
+                    if slot.fragment_tx().send(Ok(piece)).is_err() {
+                        finish_slot(&mut ctx, slot);
+                        continue;
+                    }
+                }
+                Err(e) => {
+                    let _ = slot.fragment_tx().send(Err(format!("failed to decode token: {e}")));
+                    finish_slot(&mut ctx, slot);
+                    continue;
+                }
             }
-            let output_bytes = model.token_to_bytes(token, Special::Tokenize)?;
-            let mut output_string = String::with_capacity(32);
-            let _ = decoder.decode_to_string(&output_bytes, &mut output_string, false);
-            output.push_str(&output_string);
-            batch.clear();
-            batch.add(token, n_cur, &[0], true)?;
-            n_cur += 1;
-            ctx.decode(&mut batch).with_context(|| "failed to eval")?;
+            slot.pending_token = Some(token);
         }
+    }
+}
+
+/// A single loaded model together with the scheduler serving it and the
+/// prompt template to use for it. When one model covers more than one
+/// direction, each of its `(source_lang, target_lang)` keys in
+/// [`TranslationModel::models`] holds its own entry, and those entries share
+/// the same model and scheduler.
+struct LoadedModel {
+    model: Arc<LlamaModel>,
+    scheduler: SlotScheduler,
+    prompt_template: String,
+}
+
+struct TranslationModel {
+    args: Args,
+    models: HashMap<(String, String), LoadedModel>,
+    /// Maps an OpenAI-facing model id (e.g. `kulyk-uk-en`) to the direction
+    /// it serves, for the `/v1/chat/completions` facade.
+    model_aliases: HashMap<String, (String, String)>,
+}
+
+impl TranslationModel {
+    fn new(args: Args) -> Result<Self> {
+        let registry = load_registry_config(&args.config)?;
+        let backend = Arc::new(LlamaBackend::init()?);
+
+        let mut models = HashMap::new();
+        let mut model_aliases = HashMap::new();
+        for entry in registry.models {
+            if entry.pairs.is_empty() {
+                bail!("model {} does not declare any language pairs", entry.path.display());
+            }
+
+            let model = Arc::new(
+                LlamaModel::load_from_file(&backend, &entry.path, &model_params(&args, entry.gpu_layers))
+                    .with_context(|| format!("unable to load model {}", entry.path.display()))?,
+            );
+
+            let joined_pairs = entry
+                .pairs
+                .iter()
+                .map(|p| format!("{}-{}", p.source_lang, p.target_lang))
+                .collect::<Vec<_>>()
+                .join(",");
+
+            let scheduler = SlotScheduler::spawn(
+                model.clone(),
+                backend.clone(),
+                entry.n_parallel.unwrap_or(args.n_parallel.unwrap_or(4)),
+                entry.ctx_size.or(args.ctx_size),
+                entry.threads.or(args.threads),
+                entry.threads_batch.or(args.threads_batch),
+                joined_pairs.clone(),
+            )?;
+
+            let alias = entry.name.clone().unwrap_or(joined_pairs);
+            let primary_pair = &entry.pairs[0];
+            model_aliases.insert(
+                alias,
+                (primary_pair.source_lang.clone(), primary_pair.target_lang.clone()),
+            );
+
+            for pair in entry.pairs {
+                models.insert(
+                    (pair.source_lang, pair.target_lang),
+                    LoadedModel {
+                        model: model.clone(),
+                        scheduler: scheduler.clone(),
+                        prompt_template: entry.prompt_template.clone(),
+                    },
+                );
+            }
+        }
+
+        Ok(Self {
+            args,
+            models,
+            model_aliases,
+        })
+    }
+
+    fn lookup(&self, source_lang: &str, target_lang: &str) -> Result<&LoadedModel> {
+        self.models
+            .get(&(source_lang.to_string(), target_lang.to_string()))
+            .ok_or_else(|| {
+                anyhow!(
+                    "Unsupported translation direction: {} to {}",
+                    source_lang,
+                    target_lang
+                )
+            })
+    }
+
+    fn model_for(&self, source_lang: &str, target_lang: &str) -> Result<&Arc<LlamaModel>> {
+        Ok(&self.lookup(source_lang, target_lang)?.model)
+    }
+
+    /// Resolves an OpenAI-facing model id to the `(source_lang, target_lang)`
+    /// direction it maps to.
+    fn chat_direction(&self, model_name: &str) -> Result<(String, String)> {
+        self.model_aliases
+            .get(model_name)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown model: {model_name}"))
+    }
+
+    fn prepare(&self, req: &TranslateRequest) -> Result<(&SlotScheduler, String, i32, SamplingParams)> {
+        info!(
+            "Translating text from '{}' to '{}'",
+            req.source_lang, req.target_lang
+        );
+
+        let loaded = self.lookup(&req.source_lang, &req.target_lang)?;
+        let prompt = fill_prompt(&loaded.prompt_template, &req.text);
+        info!("prompt: {}", prompt);
+
+        let n_len = req.n_len.unwrap_or(self.args.n_len);
+        let sampling = SamplingParams::resolve(&self.args, req)?;
+
+        Ok((&loaded.scheduler, prompt, n_len, sampling))
+    }
 
-        Ok(output.trim().to_string())
+    async fn translate(&self, req: &TranslateRequest) -> Result<String> {
+        let (scheduler, prompt, n_len, sampling) = self.prepare(req)?;
+        let rx = Self::submit(scheduler, prompt, n_len, sampling)?;
+        drain_stream(rx).await
     }
+
+    /// Same as [`Self::translate`], but returns the raw per-token fragment
+    /// stream instead of waiting for the full translation to finish.
+    fn translate_stream(
+        &self,
+        req: &TranslateRequest,
+    ) -> Result<tokio_mpsc::UnboundedReceiver<Result<String, String>>> {
+        let (scheduler, prompt, n_len, sampling) = self.prepare(req)?;
+        Self::submit(scheduler, prompt, n_len, sampling)
+    }
+
+    fn submit(
+        scheduler: &SlotScheduler,
+        prompt: String,
+        n_len: i32,
+        sampling: SamplingParams,
+    ) -> Result<tokio_mpsc::UnboundedReceiver<Result<String, String>>> {
+        let (tx, rx) = tokio_mpsc::unbounded_channel();
+        scheduler.submit(GenerationRequest {
+            prompt,
+            n_len,
+            sampling,
+            fragment_tx: tx,
+        })?;
+        Ok(rx)
+    }
+
+    /// Translates every item in one go. All prompts are submitted to their
+    /// scheduler up front so it can fold as many of them as fit into a
+    /// shared `LlamaBatch` and decode them together; any items beyond the
+    /// free slot count simply wait in the scheduler's queue and are picked
+    /// up as earlier ones finish, rather than the caller looping over
+    /// `translate` once per item.
+    async fn translate_batch(&self, items: &[TranslateRequest]) -> Vec<Result<String>> {
+        let pending: Vec<_> = items
+            .iter()
+            .map(|req| {
+                self.prepare(req)
+                    .and_then(|(scheduler, prompt, n_len, sampling)| {
+                        Self::submit(scheduler, prompt, n_len, sampling)
+                    })
+            })
+            .collect();
+
+        join_all(pending.into_iter().map(|submitted| async move {
+            match submitted {
+                Ok(rx) => drain_stream(rx).await,
+                Err(e) => Err(e),
+            }
+        }))
+        .await
+    }
+}
+
+/// Collects every fragment from a generation stream into the final,
+/// trimmed translation.
+async fn drain_stream(mut rx: tokio_mpsc::UnboundedReceiver<Result<String, String>>) -> Result<String> {
+    let mut output = String::new();
+    while let Some(fragment) = rx.recv().await {
+        match fragment {
+            Ok(piece) => output.push_str(&piece),
+            Err(e) => bail!(e),
+        }
+    }
+    Ok(output.trim().to_string())
 }
 
 #[derive(Deserialize, Debug)]
@@ -227,6 +789,22 @@ struct TranslateRequest {
     text: String,
     source_lang: String,
     target_lang: String,
+    /// Sampling temperature; 0 keeps greedy decoding. When omitted, the
+    /// server's `--temperature` (default 0) is used.
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    top_k: Option<i32>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    min_p: Option<f32>,
+    #[serde(default)]
+    repeat_penalty: Option<f32>,
+    #[serde(default)]
+    seed: Option<u32>,
+    #[serde(default)]
+    n_len: Option<i32>,
 }
 
 #[derive(Serialize, Debug)]
@@ -236,18 +814,171 @@ struct TranslateResponse {
     target_lang: String,
 }
 
-fn fill_prompt(text: &str, target: &str) -> String {
-    if target == "en" {
-        format!(
-            "<|im_start|>user\nTranslate the text to English:\n{text}<|im_end|>\n<|im_start|>assistant"
-        )
-    } else if target == "uk" {
-        format!(
-            "<|im_start|>user\nTranslate the text to Ukrainian:\n{text}<|im_end|>\n<|im_start|>assistant"
-        )
-    } else {
-        panic!("Unsupported target language: {}", target);
+#[derive(Deserialize, Debug)]
+struct BatchTranslateRequest {
+    items: Vec<TranslateRequest>,
+}
+
+#[derive(Serialize, Debug)]
+struct BatchTranslateResult {
+    translated_text: Option<String>,
+    source_lang: String,
+    target_lang: String,
+    error: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct BatchTranslateResponse {
+    results: Vec<BatchTranslateResult>,
+}
+
+#[derive(Serialize, Debug)]
+struct ErrorResponse {
+    error: String,
+}
+
+fn error_response(status: StatusCode, message: impl std::fmt::Display) -> axum::response::Response {
+    (status, Json(ErrorResponse { error: message.to_string() })).into_response()
+}
+
+#[derive(Deserialize, Debug)]
+struct TokenizeRequest {
+    text: String,
+    source_lang: String,
+    target_lang: String,
+}
+
+#[derive(Serialize, Debug)]
+struct TokenizeResponse {
+    tokens: Vec<i32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct DetokenizeRequest {
+    tokens: Vec<i32>,
+    source_lang: String,
+    target_lang: String,
+}
+
+#[derive(Serialize, Debug)]
+struct DetokenizeResponse {
+    text: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct CountRequest {
+    text: String,
+    source_lang: String,
+    target_lang: String,
+}
+
+#[derive(Serialize, Debug)]
+struct CountResponse {
+    count: usize,
+}
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+struct ChatMessage {
+    role: String,
+    content: String,
+}
+
+#[derive(Deserialize, Debug)]
+struct ChatCompletionRequest {
+    model: String,
+    messages: Vec<ChatMessage>,
+    #[serde(default)]
+    stream: bool,
+    #[serde(default)]
+    temperature: Option<f32>,
+    #[serde(default)]
+    top_p: Option<f32>,
+    #[serde(default)]
+    max_tokens: Option<i32>,
+    #[serde(default)]
+    seed: Option<u32>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionChoice {
+    index: u32,
+    message: ChatMessage,
+    finish_reason: String,
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionUsage {
+    prompt_tokens: usize,
+    completion_tokens: usize,
+    total_tokens: usize,
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionResponse {
+    id: String,
+    object: String,
+    model: String,
+    choices: Vec<ChatCompletionChoice>,
+    usage: ChatCompletionUsage,
+}
+
+#[derive(Serialize, Debug, Default)]
+struct ChatCompletionChunkDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    role: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionChunkChoice {
+    index: u32,
+    delta: ChatCompletionChunkDelta,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    finish_reason: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct ChatCompletionChunk {
+    id: String,
+    object: String,
+    model: String,
+    choices: Vec<ChatCompletionChunkChoice>,
+}
+
+static COMPLETION_COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// A best-effort unique id for a chat completion, in the `chatcmpl-...`
+/// shape OpenAI clients expect.
+fn completion_id() -> String {
+    let n = COMPLETION_COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    format!("chatcmpl-{millis:x}{n:x}")
+}
+
+/// Turns the generated tokens for `ids` back into text, the same way the
+/// decode loop in [`scheduler_loop`] turns sampled tokens into fragments.
+fn detokenize(model: &LlamaModel, ids: &[i32]) -> Result<String> {
+    let mut decoder = encoding_rs::UTF_8.new_decoder();
+    let mut output = String::new();
+    for &id in ids {
+        let bytes = model
+            .token_to_bytes(LlamaToken(id), Special::Tokenize)
+            .with_context(|| format!("failed to detokenize token {id}"))?;
+        let mut piece = String::with_capacity(32);
+        let _ = decoder.decode_to_string(&bytes, &mut piece, false);
+        output.push_str(&piece);
     }
+    Ok(output)
+}
+
+/// Fills a model's configured prompt template by substituting `{text}`
+/// with the source text.
+fn fill_prompt(template: &str, text: &str) -> String {
+    template.replace("{text}", text)
 }
 
 /// Handler to serve the HTML frontend.
@@ -276,6 +1007,12 @@ async fn main() -> Result<()> {
     let app = Router::new()
         .route("/", get(root_handler)) // Serve the frontend
         .route("/translate", post(translate_handler))
+        .route("/translate/stream", post(translate_stream_handler))
+        .route("/translate/batch", post(translate_batch_handler))
+        .route("/tokenize", post(tokenize_handler))
+        .route("/detokenize", post(detokenize_handler))
+        .route("/count", post(count_handler))
+        .route("/v1/chat/completions", post(chat_completions_handler))
         .with_state(model)
         .layer(cors);
 
@@ -297,7 +1034,11 @@ async fn translate_handler(
 ) -> impl IntoResponse {
     info!("Received translation request: {:?}", payload);
 
-    match model.translate(&payload.text, &payload.source_lang, &payload.target_lang) {
+    if let Err(e) = model.lookup(&payload.source_lang, &payload.target_lang) {
+        return error_response(StatusCode::BAD_REQUEST, e);
+    }
+
+    match model.translate(&payload).await {
         Ok(translated_text) => {
             let response = TranslateResponse {
                 translated_text,
@@ -308,11 +1049,318 @@ async fn translate_handler(
         }
         Err(e) => {
             error!("Translation failed: {:?}", e);
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                format!("Translation failed: {}", e),
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Translation failed: {e}"))
+        }
+    }
+}
+
+/// API handler that streams partial translation output over SSE as tokens
+/// are produced, instead of waiting for the full translation to finish.
+async fn translate_stream_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<TranslateRequest>,
+) -> impl IntoResponse {
+    info!("Received streaming translation request: {:?}", payload);
+
+    if let Err(e) = model.lookup(&payload.source_lang, &payload.target_lang) {
+        return error_response(StatusCode::BAD_REQUEST, e);
+    }
+
+    let stream: Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>> =
+        match model.translate_stream(&payload) {
+            Ok(rx) => {
+                let fragments = UnboundedReceiverStream::new(rx).map(|fragment| {
+                    Ok(match fragment {
+                        Ok(piece) => Event::default().event("token").data(piece),
+                        Err(e) => Event::default().event("error").data(e),
+                    })
+                });
+                let done = tokio_stream::once(Ok(Event::default().event("done").data("")));
+                Box::pin(fragments.chain(done))
+            }
+            Err(e) => {
+                error!("Streaming translation failed to start: {:?}", e);
+                Box::pin(tokio_stream::once(Ok(Event::default()
+                    .event("error")
+                    .data(e.to_string()))))
+            }
+        };
+
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+/// API handler that translates many items in a single call, decoding them
+/// together instead of requiring one `/translate` call per item.
+async fn translate_batch_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<BatchTranslateRequest>,
+) -> impl IntoResponse {
+    info!(
+        "Received batch translation request with {} item(s)",
+        payload.items.len()
+    );
+
+    let translations = model.translate_batch(&payload.items).await;
+
+    let results = payload
+        .items
+        .into_iter()
+        .zip(translations)
+        .map(|(item, result)| match result {
+            Ok(translated_text) => BatchTranslateResult {
+                translated_text: Some(translated_text),
+                source_lang: item.source_lang,
+                target_lang: item.target_lang,
+                error: None,
+            },
+            Err(e) => {
+                error!("Batch item translation failed: {:?}", e);
+                BatchTranslateResult {
+                    translated_text: None,
+                    source_lang: item.source_lang,
+                    target_lang: item.target_lang,
+                    error: Some(e.to_string()),
+                }
+            }
+        })
+        .collect();
+
+    (StatusCode::OK, Json(BatchTranslateResponse { results })).into_response()
+}
+
+/// API handler that tokenizes text with the model for the given direction.
+async fn tokenize_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<TokenizeRequest>,
+) -> impl IntoResponse {
+    let model = match model.model_for(&payload.source_lang, &payload.target_lang) {
+        Ok(model) => model,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    match model.str_to_token(&payload.text, AddBos::Always) {
+        Ok(tokens) => (
+            StatusCode::OK,
+            Json(TokenizeResponse {
+                tokens: tokens.into_iter().map(|t| t.0).collect(),
+            }),
+        )
+            .into_response(),
+        Err(e) => {
+            error!("Tokenization failed: {:?}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Tokenization failed: {e}"))
+        }
+    }
+}
+
+/// API handler that turns token ids back into text for the given direction.
+async fn detokenize_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<DetokenizeRequest>,
+) -> impl IntoResponse {
+    let model = match model.model_for(&payload.source_lang, &payload.target_lang) {
+        Ok(model) => model,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    match detokenize(model, &payload.tokens) {
+        Ok(text) => (StatusCode::OK, Json(DetokenizeResponse { text })).into_response(),
+        Err(e) => {
+            error!("Detokenization failed: {:?}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Detokenization failed: {e}"))
+        }
+    }
+}
+
+/// API handler that reports how many tokens `text` would take for the given
+/// direction, so callers can pre-check length against `n_len` before
+/// submitting a translation.
+async fn count_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<CountRequest>,
+) -> impl IntoResponse {
+    let model = match model.model_for(&payload.source_lang, &payload.target_lang) {
+        Ok(model) => model,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    match model.str_to_token(&payload.text, AddBos::Always) {
+        Ok(tokens) => (StatusCode::OK, Json(CountResponse { count: tokens.len() })).into_response(),
+        Err(e) => {
+            error!("Token count failed: {:?}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Token count failed: {e}"))
+        }
+    }
+}
+
+fn token_count(model: &TranslationModel, source_lang: &str, target_lang: &str, text: &str) -> usize {
+    model
+        .model_for(source_lang, target_lang)
+        .ok()
+        .and_then(|m| m.str_to_token(text, AddBos::Always).ok())
+        .map(|tokens| tokens.len())
+        .unwrap_or(0)
+}
+
+/// API handler implementing an OpenAI-compatible chat completion facade on
+/// top of the translator, so existing OpenAI client libraries can drive it
+/// unchanged. The `model` field selects the translation direction and the
+/// last user message is taken as the source text.
+async fn chat_completions_handler(
+    State(model): State<Arc<TranslationModel>>,
+    Json(payload): Json<ChatCompletionRequest>,
+) -> axum::response::Response {
+    info!(
+        "Received chat completion request for model '{}'",
+        payload.model
+    );
+
+    let (source_lang, target_lang) = match model.chat_direction(&payload.model) {
+        Ok(pair) => pair,
+        Err(e) => return error_response(StatusCode::BAD_REQUEST, e),
+    };
+
+    let text = match payload.messages.iter().rev().find(|m| m.role == "user") {
+        Some(m) => m.content.clone(),
+        None => {
+            return error_response(
+                StatusCode::BAD_REQUEST,
+                "messages must include at least one message with role 'user'",
             )
-                .into_response()
+        }
+    };
+
+    let translate_req = TranslateRequest {
+        text,
+        source_lang,
+        target_lang,
+        temperature: payload.temperature,
+        top_k: None,
+        top_p: payload.top_p,
+        min_p: None,
+        repeat_penalty: None,
+        seed: payload.seed,
+        n_len: payload.max_tokens,
+    };
+
+    if payload.stream {
+        chat_completion_stream(model, payload.model, translate_req).await
+    } else {
+        chat_completion_once(model, payload.model, translate_req).await
+    }
+}
+
+async fn chat_completion_once(
+    model: Arc<TranslationModel>,
+    model_name: String,
+    req: TranslateRequest,
+) -> axum::response::Response {
+    let prompt_tokens = token_count(&model, &req.source_lang, &req.target_lang, &req.text);
+
+    match model.translate(&req).await {
+        Ok(translated_text) => {
+            let completion_tokens =
+                token_count(&model, &req.source_lang, &req.target_lang, &translated_text);
+            let response = ChatCompletionResponse {
+                id: completion_id(),
+                object: "chat.completion".to_string(),
+                model: model_name,
+                choices: vec![ChatCompletionChoice {
+                    index: 0,
+                    message: ChatMessage {
+                        role: "assistant".to_string(),
+                        content: translated_text,
+                    },
+                    finish_reason: "stop".to_string(),
+                }],
+                usage: ChatCompletionUsage {
+                    prompt_tokens,
+                    completion_tokens,
+                    total_tokens: prompt_tokens + completion_tokens,
+                },
+            };
+            (StatusCode::OK, Json(response)).into_response()
+        }
+        Err(e) => {
+            error!("Chat completion failed: {:?}", e);
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Chat completion failed: {e}"))
         }
     }
 }
+
+async fn chat_completion_stream(
+    model: Arc<TranslationModel>,
+    model_name: String,
+    req: TranslateRequest,
+) -> axum::response::Response {
+    let rx = match model.translate_stream(&req) {
+        Ok(rx) => rx,
+        Err(e) => {
+            error!("Chat completion stream failed to start: {:?}", e);
+            return error_response(StatusCode::INTERNAL_SERVER_ERROR, format!("Chat completion failed: {e}"));
+        }
+    };
+
+    let id = completion_id();
+
+    let head = tokio_stream::once(Ok(chunk_event(&ChatCompletionChunk {
+        id: id.clone(),
+        object: "chat.completion.chunk".to_string(),
+        model: model_name.clone(),
+        choices: vec![ChatCompletionChunkChoice {
+            index: 0,
+            delta: ChatCompletionChunkDelta {
+                role: Some("assistant".to_string()),
+                content: None,
+            },
+            finish_reason: None,
+        }],
+    })));
+
+    let fragment_id = id.clone();
+    let fragment_model = model_name.clone();
+    let fragments = UnboundedReceiverStream::new(rx).map(move |fragment| {
+        let event = match fragment {
+            Ok(piece) => chunk_event(&ChatCompletionChunk {
+                id: fragment_id.clone(),
+                object: "chat.completion.chunk".to_string(),
+                model: fragment_model.clone(),
+                choices: vec![ChatCompletionChunkChoice {
+                    index: 0,
+                    delta: ChatCompletionChunkDelta {
+                        role: None,
+                        content: Some(piece),
+                    },
+                    finish_reason: None,
+                }],
+            }),
+            Err(e) => Event::default().data(
+                serde_json::to_string(&serde_json::json!({ "error": { "message": e } }))
+                    .unwrap_or_default(),
+            ),
+        };
+        Ok(event)
+    });
+
+    let tail = tokio_stream::iter(vec![
+        Ok(chunk_event(&ChatCompletionChunk {
+            id,
+            object: "chat.completion.chunk".to_string(),
+            model: model_name,
+            choices: vec![ChatCompletionChunkChoice {
+                index: 0,
+                delta: ChatCompletionChunkDelta::default(),
+                finish_reason: Some("stop".to_string()),
+            }],
+        })),
+        Ok(Event::default().data("[DONE]")),
+    ]);
+
+    let stream: Pin<Box<dyn Stream<Item = Result<Event, Infallible>> + Send>> =
+        Box::pin(head.chain(fragments).chain(tail));
+    Sse::new(stream).keep_alive(KeepAlive::default()).into_response()
+}
+
+fn chunk_event(chunk: &ChatCompletionChunk) -> Event {
+    Event::default().data(serde_json::to_string(chunk).unwrap_or_default())
+}